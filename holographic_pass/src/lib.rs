@@ -4,8 +4,31 @@ use sha2::{Sha256, Digest};
 use rand::{Rng, thread_rng};
 use std::{thread, time::Duration};
 use zeroize::Zeroize; // [Security Fix #5] 引入内存擦除特性
+use std::collections::HashMap;
+use std::cmp::Ordering;
 
-const MAX_STRING_LEN: usize = 4096; 
+const MAX_STRING_LEN: usize = 4096;
+
+/// 候选素数的字节长度（1024-bit = 128 字节）
+const CANDIDATE_BYTES: usize = 128;
+
+/// [Fix #1] BLAKE3 `derive_key` 的固定上下文串。
+/// BLAKE3 要求 context 是硬编码、全局唯一的常量；运行时的 `domain_context`
+/// 应作为派生密钥的输入（key material），而不是 context 本身。
+const BLAKE3_CONTEXT: &str = "M-Patek/SIG-HA holographic_pass v1 hash_to_prime";
+
+/// [Fix #2] Wesolowski Fiat–Shamir 挑战 `l` 的位宽：安全参数 λ ≈ 128 位。
+const CHALLENGE_BITS: usize = 128;
+const CHALLENGE_BYTES: usize = CHALLENGE_BITS / 8;
+
+/// [Fix #1] 哈希算法选择器
+/// 默认走 BLAKE3 的单遍 XOF 路径（SIMD 吞吐更适合 200k 次的搜索循环），
+/// 同时保留 SHA-256 以兼容已持久化的历史快照哈希。
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum HashAlgo {
+    Sha256,
+    Blake3,
+}
 
 #[pymodule]
 fn holographic_core(_py: Python, m: &PyModule) -> PyResult<()> {
@@ -22,22 +45,47 @@ struct RustAccumulator {
     generator: Integer,
     op_count: u64,
     max_op_limit: u64,
-    domain_context: String, 
+    domain_context: String,
+    hash_algo: HashAlgo,
+    // [Fix #2] 纯累加路径：所有注入素数的乘积，与结构性 G^H(depth) 扰动分开记账，
+    // 以便为 Wesolowski 指数证明保留一个干净的指数 x。
+    accumulated_exponent: Integer,
+    // [Fix #5] 成员见证子系统，均建立在干净的指数累加路径之上（与 G^H 扰动无关）：
+    //   accumulator_value = generator^accumulated_exponent mod n
+    //   members: agent_id -> (该成员素数, 当前见证)
+    accumulator_value: Integer,
+    members: HashMap<String, (Integer, Integer)>,
 }
 
 #[pymethods]
 impl RustAccumulator {
+    /// `hash_algo` 可选 "blake3"（默认）或 "sha256"。旧快照若用 SHA-256 生成，
+    /// 读取时显式传入 "sha256" 即可复现相同的 hash_to_prime 结果。
     #[new]
-    fn new(modulus_str: String, generator_str: String, max_depth: u64, domain: String) -> PyResult<Self> {
+    #[pyo3(signature = (modulus_str, generator_str, max_depth, domain, hash_algo=None))]
+    fn new(modulus_str: String, generator_str: String, max_depth: u64, domain: String, hash_algo: Option<String>) -> PyResult<Self> {
         Self::_validate_input(&modulus_str)?;
         Self::_validate_input(&generator_str)?;
         Self::_validate_input(&domain)?;
-        
+
         let m = Integer::from_str_radix(&modulus_str, 10)
             .map_err(|_| pyo3::exceptions::PyValueError::new_err("Invalid modulus format"))?;
         let g = Integer::from_str_radix(&generator_str, 10)
             .map_err(|_| pyo3::exceptions::PyValueError::new_err("Invalid generator format"))?;
-        
+
+        let algo = match hash_algo.as_deref() {
+            None | Some("blake3") => HashAlgo::Blake3,
+            Some("sha256") => HashAlgo::Sha256,
+            Some(other) => {
+                return Err(pyo3::exceptions::PyValueError::new_err(
+                    format!("Unknown hash_algo '{}', expected 'blake3' or 'sha256'", other)
+                ));
+            }
+        };
+
+        // 空乘积时 x = 1，故干净累加器初值 A = generator^1 = generator。
+        let g_clone = g.clone();
+
         Ok(RustAccumulator {
             modulus: m,
             current_t: Integer::from(2),
@@ -47,6 +95,10 @@ impl RustAccumulator {
             op_count: 0,
             max_op_limit: 1_000_000,
             domain_context: domain,
+            hash_algo: algo,
+            accumulated_exponent: Integer::from(1),
+            accumulator_value: g_clone,
+            members: HashMap::new(),
         })
     }
 
@@ -54,25 +106,30 @@ impl RustAccumulator {
     /// 在 Rust 层生成 p, q 并计算 n，利用 Rust 的所有权机制确保 p, q 离开作用域后被清理
     /// 相比 Python 的 del，这里的内存管理更加确定
     #[staticmethod]
-    fn generate_safe_modulus(bit_length: u32) -> String {
+    fn generate_safe_modulus(bit_length: u32) -> PyResult<(String, String)> {
         let mut rng = RandState::new();
         let seed = Integer::from(thread_rng().gen::<u64>()); // Random seed
         rng.seed(&seed);
-        
-        // 生成两个 bit_length/2 的大素数
-        // rug/GMP 的 next_prime 结合 random_bits 足够安全用于一般场景
-        let mut p = Integer::from(Integer::random_bits(bit_length / 2, &mut rng)).next_prime();
-        let mut q = Integer::from(Integer::random_bits(bit_length / 2, &mut rng)).next_prime();
-        
+
+        // [Fix #4] 生成真正的安全素数 p = 2p'+1。
+        // 仅用 next_prime() 得到的是任意素数，Z_n* 中会混入大量小阶元素，
+        // 使累加值面临低阶/子群攻击与伪造见证。安全素数把群结构收束到一个大素数阶子群。
+        let mut p = Self::_find_safe_prime(bit_length / 2, &mut rng)?;
+        let mut q = Self::_find_safe_prime(bit_length / 2, &mut rng)?;
+
         let n = Integer::from(&p * &q);
-        
+
+        // [Fix #4] 选取落在大素数阶二次剩余子群内的生成元：
+        // 平方一个随机元素即可落入 QR 子群，拒绝 0/±1 这些平凡/小阶元素。
+        let generator = Self::_pick_qr_generator(&n, &mut rng);
+
         // Zeroize p and q explicitly (Best Effort with rug)
         // rug 没有直接 impl Zeroize，但我们可以通过重写来覆盖
         // 这里依靠 Rust 的 Drop 机制，且不再将 p,q 暴露给 Python
         drop(p);
         drop(q);
-        
-        n.to_string_radix(10)
+
+        Ok((n.to_string_radix(10), generator.to_string_radix(10)))
     }
 
     fn get_state(&self) -> String {
@@ -89,8 +146,16 @@ impl RustAccumulator {
 
     fn hash_to_prime(&mut self, agent_id: String) -> PyResult<String> {
         Self::_validate_input(&agent_id)?;
-        self._inject_heavy_jitter(); // [Fix #3] 增强版 Jitter
-        
+
+        match self.hash_algo {
+            HashAlgo::Sha256 => self._hash_to_prime_sha256(&agent_id),
+            HashAlgo::Blake3 => self._hash_to_prime_blake3(&agent_id),
+        }
+    }
+
+    /// [兼容路径] 四路 SHA-256 拼接出 1024-bit 候选，失败时整体重算。
+    /// 保留它是为了能复现旧版本生成、并已写入快照的素数。
+    fn _hash_to_prime_sha256(&self, agent_id: &str) -> PyResult<String> {
         let mut nonce = 0u64;
         let prefix = format!("{}:", self.domain_context);
         let prefix_bytes = prefix.as_bytes();
@@ -108,7 +173,41 @@ impl RustAccumulator {
             }
 
             let mut candidate = Integer::from_digits(&candidate_bytes, Order::Msf);
-            candidate.set_bit(1023, true); 
+            candidate.set_bit(1023, true);
+            candidate.set_bit(0, true);
+
+            if candidate.is_probably_prime(64) != rug::integer::IsPrime::No {
+                return Ok(candidate.to_string_radix(10));
+            }
+
+            nonce += 1;
+            if nonce > 200_000 {
+                 return Err(pyo3::exceptions::PyRuntimeError::new_err("Prime generation timeout (DoS protection)"));
+            }
+        }
+    }
+
+    /// [Fix #1] BLAKE3 单遍 XOF 路径
+    /// context 用固定常量 `BLAKE3_CONTEXT`（BLAKE3 对 context 的惯用要求），
+    /// `domain_context` 作为 `derive_key` 的密钥输入提供真正的域分离（取代脆弱的
+    /// "domain:" 字符串前缀）；keyed hasher 仅吸收一次 `agent_id`，其 XOF 终结器被
+    /// 持续挤出——每个候选挤出 128 字节填满 1024-bit，失败时继续挤出后续 XOF 输出，
+    /// 而不是从头重算哈希。`nonce` 只是失败重试的计数器（用于 DoS 上限）。
+    fn _hash_to_prime_blake3(&self, agent_id: &str) -> PyResult<String> {
+        let key = blake3::derive_key(BLAKE3_CONTEXT, self.domain_context.as_bytes());
+        let mut hasher = blake3::Hasher::new_keyed(&key);
+        hasher.update(agent_id.as_bytes());
+        let mut xof = hasher.finalize_xof();
+
+        let mut buf = [0u8; CANDIDATE_BYTES];
+        let mut nonce = 0u64;
+
+        loop {
+            // 继续挤出 XOF 流推进重试，无需重建 hasher。
+            xof.fill(&mut buf);
+
+            let mut candidate = Integer::from_digits(&buf, Order::Msf);
+            candidate.set_bit(1023, true);
             candidate.set_bit(0, true);
 
             if candidate.is_probably_prime(64) != rug::integer::IsPrime::No {
@@ -116,7 +215,7 @@ impl RustAccumulator {
             }
 
             nonce += 1;
-            if nonce > 200_000 { 
+            if nonce > 200_000 {
                  return Err(pyo3::exceptions::PyRuntimeError::new_err("Prime generation timeout (DoS protection)"));
             }
         }
@@ -141,9 +240,11 @@ impl RustAccumulator {
             ));
         }
         
-        let (next_t, _) = self._compute_transition(agent_id)?;
+        let (next_t, _, p_agent) = self._compute_transition(agent_id.clone())?;
         self.current_t = next_t.clone();
         self.depth += 1;
+        // [Fix #5] 同步把该成员折入干净累加路径并更新全部见证。
+        self._fold_member(&agent_id, &p_agent);
         Ok(next_t.to_string_radix(10))
     }
 
@@ -161,7 +262,9 @@ impl RustAccumulator {
         Self::_validate_input(&prev_snapshot_hash)?;
         self._check_op_limit()?;
         
-        let (next_t, next_depth) = self._compute_transition(agent_id)?;
+        let (next_t, next_depth, p_agent) = self._compute_transition(agent_id.clone())?;
+        // [Fix #5] 折入干净累加路径并增量更新见证。
+        self._fold_member(&agent_id, &p_agent);
 
         if next_depth >= self.max_depth {
             let t_str = next_t.to_string_radix(10);
@@ -176,7 +279,10 @@ impl RustAccumulator {
             
             self.current_t = new_seed.clone();
             self.depth = 0;
-            
+
+            // [Fix #5] 快照翻转后批量刷新所有在册成员的见证。
+            let _ = self.refresh_all_witnesses();
+
             let snapshot_info = format!(
                 r#"{{"segment_id": {}, "final_t": "{}", "snapshot_hash": "{}", "prev_hash": "{}"}}"#,
                 segment_id, t_str, snapshot_hash, prev_snapshot_hash
@@ -197,7 +303,6 @@ impl RustAccumulator {
     fn safe_merge_branches(&mut self, base_t_str: String, primes_str: Vec<String>, base_depth: u64) -> PyResult<(String, u64, u64)> {
         Self::_validate_input(&base_t_str)?;
         self._check_op_limit()?;
-        self._inject_heavy_jitter(); 
 
         let mut current_term = Integer::from_str_radix(&base_t_str, 10)
              .map_err(|_| pyo3::exceptions::PyValueError::new_err("Invalid base_t format"))?;
@@ -251,42 +356,352 @@ impl RustAccumulator {
         Ok(result.to_string_radix(10))
     }
 
+    /// [Fix #2] 纯累加模式
+    /// 把 `agent_id` 的素数折入干净的运行指数 `x`（所有注入素数之积），
+    /// 不掺入任何 `G^H(depth)` 结构扰动，返回更新后的 `x`（十进制）。
+    /// 与 `update_state`/`update_with_snapshot` 共用同一条干净累加路径：
+    /// 经由 `_fold_member` 同步推进 `accumulated_exponent`、`accumulator_value`
+    /// 与全部见证，保持不变量 `accumulator_value == generator^accumulated_exponent`。
+    fn accumulate(&mut self, agent_id: String) -> PyResult<String> {
+        Self::_validate_input(&agent_id)?;
+        self._check_op_limit()?;
+        let p_str = self.hash_to_prime(agent_id.clone())?;
+        let p = Integer::from_str_radix(&p_str, 10).unwrap();
+        self._fold_member(&agent_id, &p);
+        Ok(self.accumulated_exponent.to_string_radix(10))
+    }
+
+    /// 当前纯累加指数 `x`（十进制）。
+    fn get_accumulated_exponent(&self) -> String {
+        self.accumulated_exponent.to_string_radix(10)
+    }
+
+    /// [Fix #2] Wesolowski 指数证明 (Proof of Exponentiation)
+    /// 对 `y = base^x mod n`，由 `(base, y)` 哈希出 λ 位素数挑战 `l`，令 `q = x div l`、
+    /// `r = x mod l`，证明为 `pi = base^q mod n`。返回 `(y, pi, r)`——其中 `r < l`
+    /// 是一个小（≈λ 位）余数，验证方据此无需接收或约化完整的 `x`（它是数千个
+    /// 1024-bit 素数之积）。挑战 `l` 由验证方以相同规则从 `(base, y)` 复现。
+    fn prove_exponentiation(&mut self, base_str: String, x_str: String) -> PyResult<(String, String, String)> {
+        Self::_validate_input(&base_str)?;
+        Self::_validate_input(&x_str)?;
+
+        let base = Integer::from_str_radix(&base_str, 10)
+            .map_err(|_| pyo3::exceptions::PyValueError::new_err("Invalid base format"))?;
+        let x = Integer::from_str_radix(&x_str, 10)
+            .map_err(|_| pyo3::exceptions::PyValueError::new_err("Invalid exponent format"))?;
+
+        // 负指数会让 pow_mod 在底数不可逆时返回 Err（否则 unwrap 会触发 PanicException）；
+        // 干净累加指数本就非负，这里显式拒绝。
+        if base.cmp0() == Ordering::Less || x.cmp0() == Ordering::Less {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "base and exponent must be non-negative",
+            ));
+        }
+
+        let y = base
+            .clone()
+            .pow_mod(&x, &self.modulus)
+            .map_err(|_| pyo3::exceptions::PyValueError::new_err("Exponentiation failed"))?;
+        let l = self._challenge_prime(&base, &y)?;
+
+        let (q, r) = x.div_rem_euc(l.clone());
+        let pi = base
+            .pow_mod(&q, &self.modulus)
+            .map_err(|_| pyo3::exceptions::PyValueError::new_err("Exponentiation failed"))?;
+
+        Ok((y.to_string_radix(10), pi.to_string_radix(10), r.to_string_radix(10)))
+    }
+
+    /// [Fix #2] 静态验证：两次模幂即可确认 `y = base^x`，无需重放链，也无需完整的 `x`。
+    /// 验证方自行由 `(base, y)` 复现挑战 `l`，用证明方回传的小余数 `r`（`= x mod l`）
+    /// 检查 `pi^l * base^r ≡ y (mod n)`。复现 `l` 所需的域上下文与哈希算法随
+    /// `domain`/`hash_algo` 传入。
+    #[staticmethod]
+    #[pyo3(signature = (base_str, y_str, pi_str, r_str, modulus_str, domain, hash_algo=None))]
+    fn verify_exponentiation(
+        base_str: String,
+        y_str: String,
+        pi_str: String,
+        r_str: String,
+        modulus_str: String,
+        domain: String,
+        hash_algo: Option<String>,
+    ) -> PyResult<bool> {
+        Self::_validate_input(&base_str)?;
+        Self::_validate_input(&y_str)?;
+        Self::_validate_input(&pi_str)?;
+        Self::_validate_input(&r_str)?;
+        Self::_validate_input(&modulus_str)?;
+        Self::_validate_input(&domain)?;
+
+        let probe = Self::new(modulus_str, "2".to_string(), 0, domain, hash_algo)?;
+
+        let base = Integer::from_str_radix(&base_str, 10)
+            .map_err(|_| pyo3::exceptions::PyValueError::new_err("Invalid base format"))?;
+        let y = Integer::from_str_radix(&y_str, 10)
+            .map_err(|_| pyo3::exceptions::PyValueError::new_err("Invalid y format"))?;
+        let pi = Integer::from_str_radix(&pi_str, 10)
+            .map_err(|_| pyo3::exceptions::PyValueError::new_err("Invalid pi format"))?;
+        let r = Integer::from_str_radix(&r_str, 10)
+            .map_err(|_| pyo3::exceptions::PyValueError::new_err("Invalid remainder format"))?;
+
+        if base.cmp0() == Ordering::Less {
+            return Err(pyo3::exceptions::PyValueError::new_err("base must be non-negative"));
+        }
+
+        let l = probe._challenge_prime(&base, &y)?;
+
+        // 必须强制 0 <= r < l：否则证明方可送 r = x、pi = 1 仍通过校验，
+        // 迫使验证方重算 base^x——正是本方案要避免的重放。
+        if r.cmp0() == Ordering::Less || r >= l {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "remainder out of range: require 0 <= r < l",
+            ));
+        }
+
+        // pi^l * base^r mod n
+        let lhs = pi
+            .pow_mod(&l, &probe.modulus)
+            .map_err(|_| pyo3::exceptions::PyValueError::new_err("Exponentiation failed"))?;
+        let rhs_base = base
+            .pow_mod(&r, &probe.modulus)
+            .map_err(|_| pyo3::exceptions::PyValueError::new_err("Exponentiation failed"))?;
+        let check = (lhs * rhs_base) % &probe.modulus;
+
+        Ok(check == y)
+    }
+
+    /// [Fix #5] 当前干净累加器值 `A = generator^x mod n`（十进制）。
+    /// 验证成员身份时作为 `accumulator_value` 传入 `verify_membership`。
+    fn get_accumulator_value(&self) -> String {
+        self.accumulator_value.to_string_radix(10)
+    }
+
+    /// [Fix #5] 返回 `agent_id` 的成员见证：`base^(其余所有成员素数之积) mod n`。
+    /// 见证随成员加入被增量维护，这里直接返回当前值；未累加的 agent 返回错误。
+    fn witness_for(&self, agent_id: String) -> PyResult<String> {
+        match self.members.get(&agent_id) {
+            Some((_p, w)) => Ok(w.to_string_radix(10)),
+            None => Err(pyo3::exceptions::PyValueError::new_err(
+                "Agent has not been accumulated",
+            )),
+        }
+    }
+
+    /// [Fix #5] 批量刷新所有在册成员的见证：对每个成员由
+    /// `generator^(x / prime) mod n` 从头重算，返回 `{agent_id: witness}` 的 JSON。
+    /// 用于 `update_with_snapshot` 快照翻转后统一对齐。
+    fn refresh_all_witnesses(&mut self) -> String {
+        let n = self.modulus.clone();
+        let refreshed = self.members.len() as u64;
+        let mut out: Vec<String> = Vec::new();
+        for (id, (prime, w)) in self.members.iter_mut() {
+            let others = Integer::from(&self.accumulated_exponent / prime);
+            *w = Self::_constant_pow_mod(&self.generator, &others, &n);
+            out.push(format!(r#""{}":"{}""#, id, w.to_string_radix(10)));
+        }
+        // 全量刷新的每次模幂同样计入熔断计数，使 O(members) 的扫描反映到 op_count。
+        self.op_count += refreshed;
+        format!("{{{}}}", out.join(","))
+    }
+
+    /// [Fix #5] 静态成员验证：以相同的 hash-to-prime 规则重算 `agent_id` 的素数，
+    /// 检查 `witness^prime ≡ accumulator_value (mod n)`。
+    #[staticmethod]
+    #[pyo3(signature = (accumulator_value, witness, agent_id, domain, modulus, hash_algo=None))]
+    fn verify_membership(
+        accumulator_value: String,
+        witness: String,
+        agent_id: String,
+        domain: String,
+        modulus: String,
+        hash_algo: Option<String>,
+    ) -> PyResult<bool> {
+        Self::_validate_input(&accumulator_value)?;
+        Self::_validate_input(&witness)?;
+        Self::_validate_input(&agent_id)?;
+        Self::_validate_input(&domain)?;
+        Self::_validate_input(&modulus)?;
+
+        let mut probe = Self::new(modulus, "2".to_string(), 0, domain, hash_algo)?;
+        let n = probe.modulus.clone();
+
+        let acc = Integer::from_str_radix(&accumulator_value, 10)
+            .map_err(|_| pyo3::exceptions::PyValueError::new_err("Invalid accumulator_value format"))?;
+        let w = Integer::from_str_radix(&witness, 10)
+            .map_err(|_| pyo3::exceptions::PyValueError::new_err("Invalid witness format"))?;
+
+        let p_str = probe.hash_to_prime(agent_id)?;
+        let prime = Integer::from_str_radix(&p_str, 10).unwrap();
+
+        let check = w.pow_mod(&prime, &n).unwrap();
+        Ok(check == acc)
+    }
+
     // --- Helpers ---
 
-    fn _compute_transition(&mut self, agent_id: String) -> PyResult<(Integer, u64)> {
-        let p_str = self.hash_to_prime(agent_id)?; 
-        let p_agent = Integer::from_str_radix(&p_str, 10).unwrap(); 
+    /// [Fix #4] 安全素数搜索：抽取 `half_bits` 位候选，用 next_prime 推进到素数 p'，
+    /// 再以 Miller-Rabin 64 轮（与 is_probably_prime(64) 一致）检验 2p'+1 是否也是素数，
+    /// 直到两者同时成立。附带与 hash_to_prime 相同量级的迭代上限做 DoS 保护。
+    fn _find_safe_prime(half_bits: u32, rng: &mut RandState) -> PyResult<Integer> {
+        let mut iters = 0u64;
+        loop {
+            let p_prime = Integer::from(Integer::random_bits(half_bits, rng)).next_prime();
+            let candidate = Integer::from(&p_prime * 2) + 1;
+            if candidate.is_probably_prime(64) != rug::integer::IsPrime::No {
+                return Ok(candidate);
+            }
+
+            iters += 1;
+            if iters > 200_000 {
+                return Err(pyo3::exceptions::PyRuntimeError::new_err(
+                    "Safe prime generation timeout (DoS protection)"
+                ));
+            }
+        }
+    }
+
+    /// [Fix #4] 选取大素数阶二次剩余子群的生成元：平方一个随机元素落入 QR 子群，
+    /// 拒绝 0/±1 这些平凡或小阶元素。
+    fn _pick_qr_generator(n: &Integer, rng: &mut RandState) -> Integer {
+        let bits = n.significant_bits();
+        let one = Integer::from(1);
+        let n_minus_1 = Integer::from(n - 1);
+        loop {
+            let r = Integer::from(Integer::random_bits(bits, rng)) % n;
+            let g = r.pow_mod(&Integer::from(2), n).unwrap();
+            if g != 0 && g != one && g != n_minus_1 {
+                return g;
+            }
+        }
+    }
+
+    /// [Fix #2] 由 `(base, y)` Fiat–Shamir 哈希到素数挑战 `l`。
+    /// Wesolowski 只需 λ 位（≈128-bit）的挑战，绝不能复用 `hash_to_prime`——后者强制
+    /// bit 1023 得到完整 1024-bit 素数，会让任何 `x < l` 退化为 `q = 0`、`pi = 1`，
+    /// 验证又变回重算 `base^x`。这里在 `CHALLENGE_BITS` 宽度上取哈希字节、置最高位锁定
+    /// 位宽，再 `next_prime` 得到确定性的 λ 位素数，保证证明/验证双方一致。
+    fn _challenge_prime(&self, base: &Integer, y: &Integer) -> PyResult<Integer> {
+        let seed = format!("PoE:{}:{}", base.to_string_radix(10), y.to_string_radix(10));
+        let digest = match self.hash_algo {
+            HashAlgo::Sha256 => {
+                let full = Sha256::digest(seed.as_bytes());
+                full[..CHALLENGE_BYTES].to_vec()
+            }
+            HashAlgo::Blake3 => {
+                let key = blake3::derive_key(BLAKE3_CONTEXT, self.domain_context.as_bytes());
+                let mut hasher = blake3::Hasher::new_keyed(&key);
+                hasher.update(seed.as_bytes());
+                let mut buf = [0u8; CHALLENGE_BYTES];
+                hasher.finalize_xof().fill(&mut buf);
+                buf.to_vec()
+            }
+        };
+
+        let mut c = Integer::from_digits(&digest, Order::Msf);
+        c.set_bit((CHALLENGE_BITS - 1) as u32, true); // 锁定 λ 位宽度
+        Ok(c.next_prime())
+    }
 
-        let path_term = self.current_t.clone().pow_mod(&p_agent, &self.modulus).unwrap();
+    fn _compute_transition(&mut self, agent_id: String) -> PyResult<(Integer, u64, Integer)> {
+        let p_str = self.hash_to_prime(agent_id)?;
+        let p_agent = Integer::from_str_radix(&p_str, 10).unwrap();
+
+        // [Fix #3] 秘密素数指数走带盲化的常数时间阶梯，避免依赖 GMP 变时 pow_mod。
+        let path_term = self._blinded_pow_mod(&self.current_t.clone(), &p_agent);
         self.op_count += 1;
 
         let depth_hash_bytes = Sha256::digest(self.depth.to_string().as_bytes());
         let depth_hash_int = Integer::from_str_radix(&hex::encode(depth_hash_bytes), 16).unwrap();
-        
-        let depth_term = self.generator.clone().pow_mod(&depth_hash_int, &self.modulus).unwrap();
+
+        // depth 扰动指数是公开值，直接走常数时间阶梯即可（无需盲化）。
+        let depth_term = Self::_constant_pow_mod(&self.generator, &depth_hash_int, &self.modulus);
         self.op_count += 1;
 
         let next_t = (path_term * depth_term) % &self.modulus;
-        Ok((next_t, self.depth + 1))
+        Ok((next_t, self.depth + 1, p_agent))
     }
 
-    /// [Fix #3] 增强型随机运算干扰 (Computation-Heavy Jitter)
-    /// 使用随机底数和指数进行模幂，掩盖真实运算的功耗特征
-    fn _inject_heavy_jitter(&self) {
-        let mut rng = thread_rng();
-        // 显著增加循环次数 (1000 - 5000)，使干扰更加难以被平均
-        let loop_count = rng.gen_range(1000..5000); 
-        
-        let mut dummy = Integer::from(rng.gen::<u64>());
-        let m = Integer::from(65537);
-        let exp = Integer::from(rng.gen::<u64>());
-        
-        for _ in 0..loop_count {
-            dummy = dummy.pow_mod(&exp, &m).unwrap();
+    /// [Fix #5] 把成员素数折入干净指数累加路径并增量更新所有见证：
+    ///   - 新成员的见证 = 折入前的累加器值（即其余成员素数之积对应的 base 幂）
+    ///   - 每个既有见证升一次 `prime` 次方，以纳入新加入的素数
+    ///   - 累加器值与 accumulated_exponent 同步推进
+    /// 重复注入同一 agent_id 时仅推进累加状态，不新增成员记录。
+    fn _fold_member(&mut self, agent_id: &str, prime: &Integer) {
+        let n = self.modulus.clone();
+
+        let new_witness = self.accumulator_value.clone();
+        for (_id, (_p, w)) in self.members.iter_mut() {
+            *w = Self::_constant_pow_mod(w, prime, &n);
         }
-        // 防止编译器优化掉无用计算
-        if dummy == Integer::from(0) {
-            println!("Jitter 0");
+
+        self.accumulator_value = Self::_constant_pow_mod(&self.accumulator_value, prime, &n);
+        self.accumulated_exponent *= prime;
+
+        // 把本次折入的真实模幂工作计入熔断计数：每个既有见证各一次，外加累加器值一次。
+        // 否则驱动 N 个成员要做 O(N²) 次模幂，而熔断器只记到每次转换的 2 次结构运算。
+        self.op_count += self.members.len() as u64 + 1;
+
+        self.members
+            .entry(agent_id.to_string())
+            .or_insert_with(|| (prime.clone(), new_witness));
+    }
+
+    /// [Fix #3] 常数时间模幂 (Montgomery Ladder)
+    /// 取代原先靠 1000~5000 次哑元 pow_mod 制造噪声的 jitter 方案——那只是加噪声，
+    /// 真正的秘密相关运算仍跑在 GMP 的变时 pow_mod 上。
+    /// 本实现从最高有效位到最低位逐位处理，每个比特恒定执行两次平方和一次乘法，
+    /// 由比特值经常数时间选择决定各寄存器接收的值，而不是用数据相关分支决定执行哪种
+    /// 运算，也不用秘密比特去下标访问寄存器数组（那会留下缓存时序可观测的访存模式）。
+    /// 每个比特无条件算出 `R0²`、`R1²`、`R0·R1` 三个候选，再用无分支的 `_ct_select`
+    /// 恒定地写回 `r0` 与 `r1`：
+    ///   bit==0: (R0, R1) = (R0², R0·R1)
+    ///   bit==1: (R0, R1) = (R0·R1, R1²)
+    fn _constant_pow_mod(base: &Integer, exp: &Integer, n: &Integer) -> Integer {
+        let mut r0 = Integer::from(1);
+        let mut r1 = base.clone() % n;
+        let bits = exp.significant_bits();
+
+        for i in (0..bits).rev() {
+            let flag = Integer::from(exp.get_bit(i) as u32); // 0 或 1，参与算术而非下标
+
+            let prod = (Integer::from(&r0 * &r1)) % n;
+            let sq0 = (Integer::from(&r0 * &r0)) % n;
+            let sq1 = (Integer::from(&r1 * &r1)) % n;
+
+            // flag=0 -> (sq0, prod)；flag=1 -> (prod, sq1)。始终写回 r0、r1。
+            r0 = Self::_ct_select(&flag, &prod, &sq0);
+            r1 = Self::_ct_select(&flag, &sq1, &prod);
+        }
+        r0
+    }
+
+    /// [Fix #3] 无分支常数时间选择：`flag ∈ {0,1}`，返回 `flag==1 ? a : b`。
+    /// 用 `b + flag*(a-b)` 求值，运算序列与 `flag` 取值无关，也不对寄存器做秘密下标访问。
+    fn _ct_select(flag: &Integer, a: &Integer, b: &Integer) -> Integer {
+        Integer::from(b + &(Integer::from(flag * &Integer::from(a - b))))
+    }
+
+    /// [Fix #3] 带底数盲化的常数时间模幂
+    /// 为秘密素数指数额外做底数盲化：取与 n 互素的随机 `r`，计算
+    /// `(r*base)^exp * (r^exp)^{-1} mod n`，结果与 `base^exp` 相同但中间底数被随机化，
+    /// 进一步削弱功耗/时序侧信道。若随机底数不可逆（概率极低）则重抽。
+    fn _blinded_pow_mod(&self, base: &Integer, exp: &Integer) -> Integer {
+        let n = &self.modulus;
+        let mut rng = thread_rng();
+
+        loop {
+            let r = (Integer::from(rng.gen::<u64>()) % n) + 1;
+            let r_exp = Self::_constant_pow_mod(&r, exp, n);
+            match r_exp.clone().invert(n) {
+                Ok(inv) => {
+                    let blinded_base = (Integer::from(&r * base)) % n;
+                    let blinded = Self::_constant_pow_mod(&blinded_base, exp, n);
+                    return (blinded * inv) % n;
+                }
+                // r^exp 与 n 不互素，换一个随机底数重试
+                Err(_) => continue,
+            }
         }
     }
     
@@ -308,3 +723,56 @@ impl RustAccumulator {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RSA-2048 挑战模数，足够大以确保干净累加路径在真实群上行为一致。
+    const N: &str = "25195908475657893494027183240048398571429282126204032027777137836043662020707595556264018525880784406918290641249515082189298559149176184502808489120072844992687392807287776735971418347270261896375014971824691165077613379859095700097330459748808428401797429100642458691817195118746121515172654632282216869987549182422433637259085141865462043576798423387184774447920739934236584823824281198163815010674810451660377306056201619676256133844143603833904414952634432190114657544454178424020924616515723350778707749817125772467962926386356373289912154831438167899885040445364023527381951378636564391212010397122822120720357";
+
+    // 交织 accumulate / update_state / 快照翻转，确认成员见证在翻转后仍然通过验证。
+    // 针对 accumulate 曾只推进 accumulated_exponent 而漏更新 accumulator_value 的 desync。
+    #[test]
+    fn membership_survives_interleaved_accumulate_update_and_snapshot() {
+        // max_depth=2：第二次结构性转换即触发快照翻转与见证批量刷新。
+        let mut acc = RustAccumulator::new(
+            N.to_string(),
+            "3".to_string(),
+            2,
+            "test-domain".to_string(),
+            None,
+        )
+        .unwrap();
+
+        // 纯累加一个成员（只走 _fold_member，不改 current_t / depth）。
+        acc.accumulate("agent-A".to_string()).unwrap();
+
+        // 结构性转换注入 B（depth -> 1）。
+        let prev = acc.current_t.to_string_radix(10);
+        acc.update_state("agent-B".to_string(), prev).unwrap();
+
+        // 再来一次带快照的转换注入 C；next_depth=2>=max_depth 触发翻转 + refresh_all_witnesses。
+        let prev = acc.current_t.to_string_radix(10);
+        let (_t, rolled, _info) = acc
+            .update_with_snapshot("agent-C".to_string(), 0, "seed".to_string(), prev)
+            .unwrap();
+        assert!(rolled, "snapshot rollover should have triggered at max_depth");
+
+        // 翻转后，每个真实成员的见证都应对干净累加器值验证通过。
+        let accv = acc.get_accumulator_value();
+        for id in ["agent-A", "agent-B", "agent-C"] {
+            let w = acc.witness_for(id.to_string()).unwrap();
+            let ok = RustAccumulator::verify_membership(
+                accv.clone(),
+                w,
+                id.to_string(),
+                "test-domain".to_string(),
+                N.to_string(),
+                None,
+            )
+            .unwrap();
+            assert!(ok, "membership verification failed for {id} after rollover");
+        }
+    }
+}